@@ -0,0 +1,4 @@
+mod unique_id;
+mod walk;
+
+pub use crate::walk::{DirStats, Error, FilesizeType, Walk};
@@ -1,15 +1,63 @@
 use std::path::PathBuf;
 use std::io::{self, Write};
+use std::time::SystemTime;
 
+use chrono::{DateTime, Local};
 use clap::{crate_name, crate_version, App, AppSettings, Arg};
-use humansize::file_size_opts::{self, FileSizeOpts};
+use humansize::file_size_opts::{self, FileSizeOpts, FixedAt};
 use humansize::FileSize;
 use num_format::{Locale, ToFormattedString};
 use tabwriter::TabWriter;
 
-use diskus::{Error, FilesizeType, Walk};
+use diskus::{DirStats, Error, FilesizeType, Walk};
 
-fn build_message(path: Option<&PathBuf>, size: u64, errors: &[Error], size_format: &FileSizeOpts, raw: bool, verbose: bool) -> String {
+/// Build a `FileSizeOpts` that forces every reported size into a single, fixed
+/// unit instead of letting humansize auto-pick one. Decimal units (`KB`, `MB`, …)
+/// divide by powers of 1000, binary units (`KiB`, `MiB`, …) by powers of 1024.
+fn fixed_size_format(unit: &str) -> FileSizeOpts {
+    match unit {
+        "B" => FileSizeOpts {
+            fixed_at: FixedAt::Byte,
+            ..file_size_opts::DECIMAL
+        },
+        "KB" => FileSizeOpts {
+            fixed_at: FixedAt::Kilo,
+            ..file_size_opts::DECIMAL
+        },
+        "KiB" => FileSizeOpts {
+            fixed_at: FixedAt::Kilo,
+            ..file_size_opts::BINARY
+        },
+        "MB" => FileSizeOpts {
+            fixed_at: FixedAt::Mega,
+            ..file_size_opts::DECIMAL
+        },
+        "MiB" => FileSizeOpts {
+            fixed_at: FixedAt::Mega,
+            ..file_size_opts::BINARY
+        },
+        "GB" => FileSizeOpts {
+            fixed_at: FixedAt::Giga,
+            ..file_size_opts::DECIMAL
+        },
+        "GiB" => FileSizeOpts {
+            fixed_at: FixedAt::Giga,
+            ..file_size_opts::BINARY
+        },
+        "TB" => FileSizeOpts {
+            fixed_at: FixedAt::Tera,
+            ..file_size_opts::DECIMAL
+        },
+        "TiB" => FileSizeOpts {
+            fixed_at: FixedAt::Tera,
+            ..file_size_opts::BINARY
+        },
+        // `possible_values` guarantees we never reach this arm.
+        _ => file_size_opts::DECIMAL,
+    }
+}
+
+fn build_message(path: Option<&PathBuf>, size: u64, mtime: Option<SystemTime>, errors: &[Error], size_format: &FileSizeOpts, raw: bool, verbose: bool) -> String {
     if verbose {
         for err in errors {
             match err {
@@ -34,49 +82,141 @@ fn build_message(path: Option<&PathBuf>, size: u64, errors: &[Error], size_forma
     }
 
     let path_info = path.map(|p| format!("\t{}", p.to_string_lossy())).unwrap_or_default();
+    let time_info = mtime
+        .map(|t| format!("\t{}", DateTime::<Local>::from(t).format("%Y-%m-%d %H:%M:%S")))
+        .unwrap_or_default();
     if raw {
-        format!("{}{}", size, path_info)
+        format!("{}{}{}", size, time_info, path_info)
     } else {
         let human_readable_size = size.file_size(size_format).unwrap();
         let size_in_bytes = size.to_formatted_string(&Locale::en);
         if verbose {
-            format!("{} ({:} bytes){}", human_readable_size, size_in_bytes, path_info)
+            format!("{} ({:} bytes){}{}", human_readable_size, size_in_bytes, time_info, path_info)
         } else {
-            format!("{}{}", human_readable_size, path_info)
+            format!("{}{}{}", human_readable_size, time_info, path_info)
+        }
+    }
+}
+
+
+#[allow(clippy::too_many_arguments)]
+fn perform_top(walks: Vec<Walk>, top: usize, max_depth: Option<usize>, size_format: &FileSizeOpts, raw: bool, verbose: bool, show_time: bool) {
+    let mut tw = TabWriter::new(io::stdout()).padding(2);
+    for walk in walks {
+        let (breakdown, errors) = walk.run_breakdown(max_depth);
+        let mut entries: Vec<(PathBuf, DirStats)> = breakdown.into_iter().collect();
+        entries.sort_by(|a, b| b.1.size.cmp(&a.1.size).then_with(|| a.0.cmp(&b.0)));
+        for (i, (path, stats)) in entries.into_iter().take(top).enumerate() {
+            // Report any filesystem errors only once per walk (one walk per root,
+            // or a single walk for all roots under --aggregate) to avoid
+            // repeating the same warnings for every listed entry.
+            let errors = if i == 0 { &errors[..] } else { &[] };
+            writeln!(tw, "{}",
+                build_message(Some(&path), stats.size, show_time.then_some(stats.mtime).flatten(), errors, size_format, raw, verbose)
+            ).unwrap();
+        }
+    }
+    tw.flush().unwrap();
+}
+
+fn perform_tree(walks: Vec<Walk>, depth: Option<usize>, size_format: &FileSizeOpts, raw: bool, verbose: bool, show_time: bool) {
+    let mut tw = TabWriter::new(io::stdout()).padding(2);
+    for walk in walks {
+        let (sizes, errors) = walk.run_breakdown(None);
+        // A Walk may carry several roots (e.g. under --aggregate), so print a
+        // tree for each of them rather than only the first.
+        for (i, root) in walk.get_root_directories().iter().enumerate() {
+            // The error list is shared across the roots; only report it once.
+            let errors = if i == 0 { &errors[..] } else { &[] };
+            print_tree_node(&mut tw, &sizes, root, 0, depth, errors, size_format, raw, verbose, show_time);
         }
     }
+    tw.flush().unwrap();
 }
 
+/// Recursively print `dir` and its subdirectories (by descending aggregated
+/// size) indented under their parent, descending no deeper than `max_depth`.
+/// Sizes already bubble up, so directories below the limit still count towards
+/// their visible ancestor's total even though they are not listed individually.
+#[allow(clippy::too_many_arguments)]
+fn print_tree_node(
+    tw: &mut TabWriter<io::Stdout>,
+    sizes: &std::collections::HashMap<PathBuf, DirStats>,
+    dir: &PathBuf,
+    level: usize,
+    max_depth: Option<usize>,
+    errors: &[Error],
+    size_format: &FileSizeOpts,
+    raw: bool,
+    verbose: bool,
+    show_time: bool,
+) {
+    let stats = sizes.get(dir).copied().unwrap_or_default();
+    // Report filesystem errors only once, for the root line.
+    let errors = if level == 0 { errors } else { &[] };
+    let indent = "    ".repeat(level);
+    let rendered = build_message(None, stats.size, show_time.then_some(stats.mtime).flatten(), errors, size_format, raw, verbose);
+    writeln!(tw, "{}\t{}{}", rendered, indent, dir.to_string_lossy()).unwrap();
+
+    if max_depth.is_some_and(|d| level >= d) {
+        return;
+    }
+
+    // The tree lists directories; files still count towards their parent's size.
+    let mut children: Vec<&PathBuf> = sizes
+        .iter()
+        .filter(|(p, s)| s.is_dir && p.parent() == Some(dir.as_path()))
+        .map(|(p, _)| p)
+        .collect();
+    children.sort_by(|a, b| {
+        sizes[*b].size
+            .cmp(&sizes[*a].size)
+            .then_with(|| a.cmp(b))
+    });
+    for child in children {
+        print_tree_node(tw, sizes, child, level + 1, max_depth, errors, size_format, raw, verbose, show_time);
+    }
+}
 
-fn perform_walks(walks: Vec<Walk>, aggregate: bool, size_format: FileSizeOpts, raw: bool, verbose: bool) {
+fn perform_walks(walks: Vec<Walk>, aggregate: bool, size_format: FileSizeOpts, raw: bool, verbose: bool, show_time: bool) {
     if aggregate {
         let mut total_size = 0;
+        let mut max_mtime: Option<SystemTime> = None;
         let mut all_errors = Vec::new();
 
         for walk in walks {
-            let (size, errors) = walk.run();
+            let (size, mtime, errors) = walk.run();
             total_size += size;
+            max_mtime = latest(max_mtime, mtime);
             all_errors.extend(errors);
         }
 
         println!("{}",
-            build_message(None, total_size, &all_errors, &size_format, raw, verbose)
+            build_message(None, total_size, show_time.then_some(max_mtime).flatten(), &all_errors, &size_format, raw, verbose)
         );
     } else {
         let mut tw = TabWriter::new(io::stdout()).padding(2);
         for walk in walks {
             // each Walk knows its own root_directories
-            let (size, errors) = walk.run();
+            let (size, mtime, errors) = walk.run();
             assert_eq!(walk.get_root_directories().len(), 1, "perform_walks can only be called without aggregation with a single root directory");
             let path = &walk.get_root_directories()[0];
             writeln!(tw, "{}",
-                build_message(Some(path), size, &errors, &size_format, raw, verbose)
+                build_message(Some(path), size, show_time.then_some(mtime).flatten(), &errors, &size_format, raw, verbose)
             ).unwrap();
         }
         tw.flush().unwrap();
     }
 }
 
+/// Return the more recent of two optional modification times.
+fn latest(a: Option<SystemTime>, b: Option<SystemTime>) -> Option<SystemTime> {
+    match (a, b) {
+        (Some(a), Some(b)) => Some(a.max(b)),
+        (a, b) => a.or(b),
+    }
+}
+
 fn main() {
     let app = App::new(crate_name!())
         .setting(AppSettings::ColorAuto)
@@ -103,9 +243,12 @@ fn main() {
                 .long("size-format")
                 .takes_value(true)
                 .value_name("type")
-                .possible_values(&["decimal", "binary"])
+                .possible_values(&[
+                    "decimal", "binary", "B", "KB", "KiB", "MB", "MiB", "GB", "GiB", "TB",
+                    "TiB",
+                ])
                 .default_value("decimal")
-                .help("Output format for file sizes (decimal: MB, binary: MiB)"),
+                .help("Output format for file sizes (decimal: MB, binary: MiB, or a fixed unit such as MB/MiB)"),
         )
         .arg(
             Arg::with_name("raw")
@@ -136,6 +279,51 @@ fn main() {
             .help("Compute apparent size instead of disk usage"),
     );
 
+    let app = app.arg(
+        Arg::with_name("count-hardlinks")
+            .long("count-hardlinks")
+            .takes_value(false)
+            .help("Count hardlinked files multiple times instead of deduplicating them by inode"),
+    );
+
+    let app = app.arg(
+        Arg::with_name("time")
+            .long("time")
+            .takes_value(false)
+            .help("Show the most recent modification time found anywhere in each subtree"),
+    );
+
+    let app = app
+        .arg(
+            Arg::with_name("tree")
+                .long("tree")
+                .takes_value(false)
+                .help("Print a nested tree of directories with their aggregated sizes"),
+        )
+        .arg(
+            Arg::with_name("depth")
+                .long("depth")
+                .value_name("N")
+                .takes_value(true)
+                .help("Limit the tree to N levels below each root (used with --tree)"),
+        );
+
+    let app = app
+        .arg(
+            Arg::with_name("top")
+                .long("top")
+                .value_name("N")
+                .takes_value(true)
+                .help("Instead of a single total, print the N largest entries per path (one combined list with --aggregate), sorted by descending size (default: 10)"),
+        )
+        .arg(
+            Arg::with_name("max-depth")
+                .long("max-depth")
+                .value_name("N")
+                .takes_value(true)
+                .help("Only accumulate per-entry sizes down to this depth below each root (used with --top)"),
+        );
+
     let matches = app.get_matches();
 
     // Setting the number of threads to 3x the number of cores is a good tradeoff between
@@ -161,20 +349,39 @@ fn main() {
 
     let size_format = match matches.value_of("size-format") {
         Some("decimal") => file_size_opts::DECIMAL,
-        _ => file_size_opts::BINARY,
+        Some("binary") => file_size_opts::BINARY,
+        Some(unit) => fixed_size_format(unit),
+        None => file_size_opts::DECIMAL,
     };
 
     let raw = matches.is_present("raw");
     let verbose = matches.is_present("verbose");
     let aggregate = matches.is_present("aggregate");
+    let count_hardlinks = matches.is_present("count-hardlinks");
+    let show_time = matches.is_present("time");
     let walks: Vec<Walk> = if aggregate {
-        vec![Walk::new(&paths, num_threads, filesize_type)]
+        vec![Walk::new(&paths, num_threads, filesize_type, count_hardlinks)]
     } else {
         paths
             .iter()
-            .map(|p| Walk::new(std::slice::from_ref(p), num_threads, filesize_type))
+            .map(|p| Walk::new(std::slice::from_ref(p), num_threads, filesize_type, count_hardlinks))
             .collect()
     };
 
-    perform_walks(walks, aggregate, size_format, raw, verbose);
+    let max_depth = matches
+        .value_of("max-depth")
+        .and_then(|d| d.parse().ok());
+
+    if matches.is_present("tree") {
+        let depth = matches.value_of("depth").and_then(|d| d.parse().ok());
+        perform_tree(walks, depth, &size_format, raw, verbose, show_time);
+    } else if matches.is_present("top") {
+        let top = matches
+            .value_of("top")
+            .and_then(|n| n.parse().ok())
+            .unwrap_or(10);
+        perform_top(walks, top, max_depth, &size_format, raw, verbose, show_time);
+    } else {
+        perform_walks(walks, aggregate, size_format, raw, verbose, show_time);
+    }
 }
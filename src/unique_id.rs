@@ -0,0 +1,33 @@
+#[cfg(not(windows))]
+use std::os::unix::fs::MetadataExt;
+
+/// Identifies a file by the device it lives on and its inode number. Two
+/// directory entries that are hard links to the same file share a `UniqueID`,
+/// which lets us count their blocks only once.
+#[derive(Eq, PartialEq, Hash)]
+pub struct UniqueID {
+    device: u64,
+    inode: u64,
+}
+
+/// Build a `UniqueID` for a file, but only if it is hard-linked more than once.
+/// A file with a single link can never be reached twice during the walk, so we
+/// return `None` for it and skip the (comparatively expensive) dedup set.
+#[cfg(not(windows))]
+pub fn generate_unique_id(metadata: &std::fs::Metadata) -> Option<UniqueID> {
+    if metadata.nlink() > 1 {
+        Some(UniqueID {
+            device: metadata.dev(),
+            inode: metadata.ino(),
+        })
+    } else {
+        None
+    }
+}
+
+/// On Windows the inode identity is not available through `Metadata`, so we fall
+/// back to counting every entry and never deduplicate.
+#[cfg(windows)]
+pub fn generate_unique_id(_metadata: &std::fs::Metadata) -> Option<UniqueID> {
+    None
+}
@@ -0,0 +1,314 @@
+use std::collections::{HashMap, HashSet};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::thread;
+use std::time::SystemTime;
+
+#[cfg(not(windows))]
+use std::os::unix::fs::MetadataExt;
+
+use crossbeam_channel as channel;
+use rayon::prelude::*;
+
+use crate::unique_id::{generate_unique_id, UniqueID};
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum FilesizeType {
+    ApparentSize,
+    DiskUsage,
+}
+
+impl FilesizeType {
+    pub fn size(self, metadata: &fs::Metadata) -> u64 {
+        match self {
+            FilesizeType::ApparentSize => metadata.len(),
+            #[cfg(not(windows))]
+            FilesizeType::DiskUsage => metadata.blocks() * 512,
+            #[cfg(windows)]
+            FilesizeType::DiskUsage => metadata.len(),
+        }
+    }
+}
+
+pub enum Error {
+    NoMetadataForPath(PathBuf),
+    CouldNotReadDir(PathBuf),
+}
+
+enum Message {
+    SizeEntry(Option<UniqueID>, u64, Option<SystemTime>),
+    Error(Error),
+}
+
+enum BreakdownMessage {
+    Entry {
+        path: PathBuf,
+        size: u64,
+        is_dir: bool,
+        mtime: Option<SystemTime>,
+        unique_id: Option<UniqueID>,
+    },
+    Error(Error),
+}
+
+/// Aggregated information about a single path bucket in a breakdown: the total
+/// size accumulated into it, the most recent modification time in its subtree
+/// and whether the path itself is a directory.
+#[derive(Clone, Copy, Default)]
+pub struct DirStats {
+    pub size: u64,
+    pub mtime: Option<SystemTime>,
+    pub is_dir: bool,
+}
+
+/// Return the more recent of two optional modification times.
+fn latest(a: Option<SystemTime>, b: Option<SystemTime>) -> Option<SystemTime> {
+    match (a, b) {
+        (Some(a), Some(b)) => Some(a.max(b)),
+        (a, b) => a.or(b),
+    }
+}
+
+fn walk(
+    tx: channel::Sender<Message>,
+    entries: &[PathBuf],
+    filesize_type: FilesizeType,
+    count_hardlinks: bool,
+) {
+    entries.into_par_iter().for_each_with(tx, |tx, entry| {
+        if let Ok(metadata) = entry.symlink_metadata() {
+            // When the user opts out of deduplication we never build a unique
+            // id, so each hard link is summed separately.
+            let unique_id = if count_hardlinks {
+                None
+            } else {
+                generate_unique_id(&metadata)
+            };
+            let size = filesize_type.size(&metadata);
+            let mtime = metadata.modified().ok();
+
+            tx.send(Message::SizeEntry(unique_id, size, mtime)).unwrap();
+
+            if metadata.is_dir() {
+                let mut children = vec![];
+                match fs::read_dir(entry) {
+                    Ok(child_entries) => {
+                        for child_entry in child_entries.flatten() {
+                            children.push(child_entry.path());
+                        }
+                    }
+                    Err(_) => {
+                        tx.send(Message::Error(Error::CouldNotReadDir(entry.clone())))
+                            .unwrap();
+                    }
+                }
+
+                walk(tx.clone(), &children, filesize_type, count_hardlinks);
+            }
+        } else {
+            tx.send(Message::Error(Error::NoMetadataForPath(entry.clone())))
+                .unwrap();
+        }
+    });
+}
+
+fn walk_breakdown(
+    tx: channel::Sender<BreakdownMessage>,
+    entries: &[PathBuf],
+    filesize_type: FilesizeType,
+    count_hardlinks: bool,
+) {
+    entries.into_par_iter().for_each_with(tx, |tx, entry| {
+        if let Ok(metadata) = entry.symlink_metadata() {
+            // Deduplicate hard links exactly like the flat walk, so the
+            // per-path totals reconcile with `diskus`'s single number.
+            let unique_id = if count_hardlinks {
+                None
+            } else {
+                generate_unique_id(&metadata)
+            };
+            let size = filesize_type.size(&metadata);
+            let is_dir = metadata.is_dir();
+            let mtime = metadata.modified().ok();
+
+            tx.send(BreakdownMessage::Entry {
+                path: entry.clone(),
+                size,
+                is_dir,
+                mtime,
+                unique_id,
+            })
+            .unwrap();
+
+            if is_dir {
+                let mut children = vec![];
+                match fs::read_dir(entry) {
+                    Ok(child_entries) => {
+                        for child_entry in child_entries.flatten() {
+                            children.push(child_entry.path());
+                        }
+                    }
+                    Err(_) => {
+                        tx.send(BreakdownMessage::Error(Error::CouldNotReadDir(entry.clone())))
+                            .unwrap();
+                    }
+                }
+
+                walk_breakdown(tx.clone(), &children, filesize_type, count_hardlinks);
+            }
+        } else {
+            tx.send(BreakdownMessage::Error(Error::NoMetadataForPath(entry.clone())))
+                .unwrap();
+        }
+    });
+}
+
+/// Return `true` if `path` is one of the roots or lies below a root no deeper
+/// than `max_depth` directory levels (a root itself is at depth 0).
+fn within_depth(path: &Path, roots: &[PathBuf], max_depth: Option<usize>) -> bool {
+    roots.iter().any(|root| match path.strip_prefix(root) {
+        Ok(rel) => max_depth.is_none_or(|d| rel.components().count() <= d),
+        Err(_) => false,
+    })
+}
+
+pub struct Walk<'a> {
+    root_directories: &'a [PathBuf],
+    num_threads: usize,
+    filesize_type: FilesizeType,
+    count_hardlinks: bool,
+}
+
+impl<'a> Walk<'a> {
+    pub fn new(
+        root_directories: &'a [PathBuf],
+        num_threads: usize,
+        filesize_type: FilesizeType,
+        count_hardlinks: bool,
+    ) -> Walk<'a> {
+        Walk {
+            root_directories,
+            num_threads,
+            filesize_type,
+            count_hardlinks,
+        }
+    }
+
+    pub fn run(&self) -> (u64, Option<SystemTime>, Vec<Error>) {
+        let (tx, rx) = channel::unbounded();
+
+        let receiver_thread = thread::spawn(move || {
+            let mut total = 0;
+            let mut max_mtime: Option<SystemTime> = None;
+            let mut ids = HashSet::new();
+            let mut error_messages: Vec<Error> = Vec::new();
+            for msg in rx {
+                match msg {
+                    Message::SizeEntry(unique_id, size, mtime) => {
+                        max_mtime = latest(max_mtime, mtime);
+                        if let Some(unique_id) = unique_id {
+                            // Only count a hard-linked file the first time we
+                            // encounter its inode.
+                            if ids.insert(unique_id) {
+                                total += size;
+                            }
+                        } else {
+                            total += size;
+                        }
+                    }
+                    Message::Error(error) => error_messages.push(error),
+                }
+            }
+            (total, max_mtime, error_messages)
+        });
+
+        let pool = rayon::ThreadPoolBuilder::new()
+            .num_threads(self.num_threads)
+            .build()
+            .unwrap();
+        pool.install(|| {
+            walk(
+                tx,
+                self.root_directories,
+                self.filesize_type,
+                self.count_hardlinks,
+            )
+        });
+
+        receiver_thread.join().unwrap()
+    }
+
+    /// Like [`run`](Self::run), but instead of a single total it returns a
+    /// bucket per path within `max_depth` levels of a root. Every discovered
+    /// entry adds its size to all of its ancestor directories; files also get
+    /// their own bucket so large files surface in a whale-hunt listing. Entries
+    /// below the depth window still contribute their bytes to their visible
+    /// ancestors but do not get a bucket of their own.
+    pub fn run_breakdown(&self, max_depth: Option<usize>) -> (HashMap<PathBuf, DirStats>, Vec<Error>) {
+        let (tx, rx) = channel::unbounded();
+
+        let roots = self.root_directories.to_vec();
+        let receiver_thread = thread::spawn(move || {
+            let mut sizes: HashMap<PathBuf, DirStats> = HashMap::new();
+            let mut ids = HashSet::new();
+            let mut error_messages: Vec<Error> = Vec::new();
+            for msg in rx {
+                match msg {
+                    BreakdownMessage::Entry {
+                        path,
+                        size,
+                        is_dir,
+                        mtime,
+                        unique_id,
+                    } => {
+                        // Account a hard-linked file only the first time its
+                        // inode is seen, matching the flat walk.
+                        if let Some(unique_id) = unique_id {
+                            if !ids.insert(unique_id) {
+                                continue;
+                            }
+                        }
+
+                        // Add the entry's own block to its own bucket and to
+                        // every in-window ancestor. Seeding the bucket itself
+                        // (rather than only its ancestors) is what makes a
+                        // directory's reported size — the root's in particular —
+                        // reconcile with the flat `diskus` total. Files get a
+                        // bucket too, so large files surface in the whale hunt.
+                        for ancestor in path.ancestors() {
+                            if within_depth(ancestor, &roots, max_depth) {
+                                let bucket = sizes.entry(ancestor.to_path_buf()).or_default();
+                                bucket.size += size;
+                                bucket.mtime = latest(bucket.mtime, mtime);
+                                if ancestor == path {
+                                    bucket.is_dir = is_dir;
+                                }
+                            }
+                        }
+                    }
+                    BreakdownMessage::Error(error) => error_messages.push(error),
+                }
+            }
+            (sizes, error_messages)
+        });
+
+        let pool = rayon::ThreadPoolBuilder::new()
+            .num_threads(self.num_threads)
+            .build()
+            .unwrap();
+        pool.install(|| {
+            walk_breakdown(
+                tx,
+                self.root_directories,
+                self.filesize_type,
+                self.count_hardlinks,
+            )
+        });
+
+        receiver_thread.join().unwrap()
+    }
+
+    pub fn get_root_directories(&self) -> &[PathBuf] {
+        self.root_directories
+    }
+}
@@ -0,0 +1,48 @@
+#![cfg(unix)]
+
+use assert_cmd::Command;
+use std::fs::{self, File};
+use std::io::Write;
+use tempfile::tempdir;
+
+fn total(args: &[&str], root: &str) -> u64 {
+    let output = Command::cargo_bin("diskus")
+        .unwrap()
+        .args(args)
+        .arg(root)
+        .assert()
+        .success()
+        .get_output()
+        .stdout
+        .clone();
+    String::from_utf8_lossy(&output)
+        .split_whitespace()
+        .next()
+        .unwrap()
+        .parse()
+        .unwrap()
+}
+
+#[test]
+fn hardlinks_are_deduplicated_unless_opted_out() {
+    let tmp = tempdir().unwrap();
+    let original = tmp.path().join("original.bin");
+    File::create(&original)
+        .unwrap()
+        .write_all(&[0u8; 16384])
+        .unwrap();
+    fs::hard_link(&original, tmp.path().join("link.bin")).unwrap();
+    let root = tmp.path().to_str().unwrap();
+
+    let deduplicated = total(&["--raw"], root);
+    let counted = total(&["--raw", "--count-hardlinks"], root);
+
+    // The second link adds roughly another copy's worth of blocks only when the
+    // user opts out of deduplication.
+    assert!(
+        counted > deduplicated,
+        "counting hardlinks ({}) should exceed the deduplicated total ({})",
+        counted,
+        deduplicated
+    );
+}
@@ -0,0 +1,28 @@
+use assert_cmd::Command;
+use std::fs::File;
+use std::io::Write;
+use tempfile::tempdir;
+
+#[test]
+fn fixed_unit_forces_the_reported_unit() {
+    let tmp = tempdir().unwrap();
+    File::create(tmp.path().join("f.bin"))
+        .unwrap()
+        .write_all(&[0u8; 2048])
+        .unwrap();
+    let root = tmp.path().to_str().unwrap();
+
+    let output = Command::cargo_bin("diskus")
+        .unwrap()
+        .args(["--size-format", "KB", root])
+        .assert()
+        .success()
+        .get_output()
+        .stdout
+        .clone();
+    let output = String::from_utf8_lossy(&output);
+
+    // A fixed unit must be used verbatim rather than auto-picking MB/GB/…
+    assert!(output.contains("KB"), "expected a KB-formatted size: {}", output);
+    assert!(!output.contains("MB"), "unit should be fixed at KB: {}", output);
+}
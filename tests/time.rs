@@ -0,0 +1,51 @@
+use assert_cmd::Command;
+use std::fs::File;
+use std::io::Write;
+use tempfile::tempdir;
+
+fn has_timestamp(text: &str) -> bool {
+    // A rendered modification time looks like "2024-01-02 03:04:05".
+    text.split_whitespace().any(|token| {
+        let bytes = token.as_bytes();
+        bytes.len() == 10
+            && bytes[4] == b'-'
+            && bytes[7] == b'-'
+            && bytes.iter().filter(|b| b.is_ascii_digit()).count() == 8
+    })
+}
+
+#[test]
+fn time_flag_adds_a_modification_time_column() {
+    let tmp = tempdir().unwrap();
+    File::create(tmp.path().join("f.bin"))
+        .unwrap()
+        .write_all(&[0u8; 1024])
+        .unwrap();
+    let root = tmp.path().to_str().unwrap();
+
+    let without = Command::cargo_bin("diskus")
+        .unwrap()
+        .arg(root)
+        .assert()
+        .success()
+        .get_output()
+        .stdout
+        .clone();
+    assert!(
+        !has_timestamp(&String::from_utf8_lossy(&without)),
+        "no timestamp expected without --time"
+    );
+
+    let with = Command::cargo_bin("diskus")
+        .unwrap()
+        .args(["--time", root])
+        .assert()
+        .success()
+        .get_output()
+        .stdout
+        .clone();
+    assert!(
+        has_timestamp(&String::from_utf8_lossy(&with)),
+        "--time should add a modification-time column"
+    );
+}
@@ -0,0 +1,35 @@
+use assert_cmd::Command;
+use std::fs::File;
+use std::io::Write;
+use tempfile::tempdir;
+
+#[test]
+fn top_lists_a_large_file_by_name() {
+    let tmp = tempdir().unwrap();
+    File::create(tmp.path().join("small.bin"))
+        .unwrap()
+        .write_all(&[0u8; 256])
+        .unwrap();
+    File::create(tmp.path().join("big.bin"))
+        .unwrap()
+        .write_all(&[0u8; 65536])
+        .unwrap();
+    let root = tmp.path().to_str().unwrap();
+
+    let output = Command::cargo_bin("diskus")
+        .unwrap()
+        .args(["--top", "5", root])
+        .assert()
+        .success()
+        .get_output()
+        .stdout
+        .clone();
+    let output = String::from_utf8_lossy(&output);
+
+    // A whale hunt must surface individual large files, not just directories.
+    assert!(
+        output.contains("big.bin"),
+        "expected the large file in --top output: {}",
+        output
+    );
+}
@@ -0,0 +1,43 @@
+use assert_cmd::Command;
+use std::fs::{self, File};
+use std::io::Write;
+use tempfile::tempdir;
+
+fn first_size(stdout: &[u8]) -> u64 {
+    let text = String::from_utf8_lossy(stdout);
+    let line = text.lines().next().unwrap();
+    line.split_whitespace().next().unwrap().parse().unwrap()
+}
+
+#[test]
+fn tree_root_total_matches_flat_total() {
+    let tmp = tempdir().unwrap();
+    let sub = tmp.path().join("sub");
+    fs::create_dir(&sub).unwrap();
+    File::create(sub.join("f.bin"))
+        .unwrap()
+        .write_all(&[0u8; 4096])
+        .unwrap();
+    let root = tmp.path().to_str().unwrap();
+
+    let flat = Command::cargo_bin("diskus")
+        .unwrap()
+        .args(["--raw", root])
+        .assert()
+        .success()
+        .get_output()
+        .stdout
+        .clone();
+
+    let tree = Command::cargo_bin("diskus")
+        .unwrap()
+        .args(["--tree", "--raw", root])
+        .assert()
+        .success()
+        .get_output()
+        .stdout
+        .clone();
+
+    // The top line of a tree view must report the same total as the flat run.
+    assert_eq!(first_size(&flat), first_size(&tree));
+}